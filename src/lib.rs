@@ -26,8 +26,10 @@
 //! assert_eq!(q.get(&"b"), Some(&2));
 //! ```
 use std::{
-    collections::{HashMap, VecDeque},
-    hash::Hash,
+    borrow::Borrow,
+    collections::{hash_map::RandomState, HashMap, VecDeque},
+    hash::{BuildHasher, Hash},
+    num::NonZeroUsize,
 };
 
 // use generational counter to check whether entry in deque correspods to entry in hashmap.
@@ -41,14 +43,15 @@ struct GenHolder<V> {
 ///
 /// This structure preserves a logical order of entries while providing
 /// $O(1)$ average-time lookups by key.
-pub struct OrdHash<K, V> {
-    map: HashMap<K, GenHolder<V>>,
+pub struct OrdHash<K, V, S = RandomState> {
+    map: HashMap<K, GenHolder<V>, S>,
     order: VecDeque<GenHolder<K>>,
     generation: usize,
     length: usize,
+    max_len: Option<NonZeroUsize>,
 }
 
-impl<K: Eq + Hash + Clone, V> OrdHash<K, V> {
+impl<K: Eq + Hash + Clone, V> OrdHash<K, V, RandomState> {
     /// Creates a new empty `OrdHash`.
     ///
     /// The map and order queue start with zero capacity.
@@ -58,6 +61,7 @@ impl<K: Eq + Hash + Clone, V> OrdHash<K, V> {
             order: VecDeque::new(),
             generation: 0,
             length: 0,
+            max_len: None,
         }
     }
     /// Creates a new empty `OrdHash` with the specified capacity.
@@ -67,8 +71,53 @@ impl<K: Eq + Hash + Clone, V> OrdHash<K, V> {
             order: VecDeque::with_capacity(cap),
             generation: 0,
             length: 0,
+            max_len: None,
         }
     }
+    /// Creates a new empty `OrdHash` that keeps at most `cap` live entries.
+    ///
+    /// Once the live length would exceed `cap`, inserting or refreshing evicts
+    /// the oldest live entries until the map is within bound again. This turns
+    /// the map into an LRU cache; see `get_refresh()` to keep hot keys alive.
+    ///
+    /// The bound is a `NonZeroUsize`: a zero capacity would evict every entry
+    /// as soon as it was inserted, which the `entry()` API cannot express.
+    pub fn with_max_len(cap: NonZeroUsize) -> Self {
+        let mut m = Self::with_capacity(cap.get());
+        m.max_len = Some(cap);
+        m
+    }
+}
+
+impl<K: Eq + Hash + Clone, V, S: BuildHasher> OrdHash<K, V, S> {
+    /// Creates a new empty `OrdHash` that will use the given hash builder.
+    pub fn with_hasher(hasher: S) -> Self {
+        OrdHash {
+            map: HashMap::with_hasher(hasher),
+            order: VecDeque::new(),
+            generation: 0,
+            length: 0,
+            max_len: None,
+        }
+    }
+    /// Creates a new empty `OrdHash` with the given capacity and hash builder.
+    pub fn with_capacity_and_hasher(cap: usize, hasher: S) -> Self {
+        OrdHash {
+            map: HashMap::with_capacity_and_hasher(cap, hasher),
+            order: VecDeque::with_capacity(cap),
+            generation: 0,
+            length: 0,
+            max_len: None,
+        }
+    }
+    /// Sets or clears the maximum number of live entries.
+    ///
+    /// Passing `Some(cap)` immediately evicts the oldest live entries until the
+    /// map holds at most `cap` of them. Passing `None` removes the bound.
+    pub fn set_capacity(&mut self, cap: Option<NonZeroUsize>) {
+        self.max_len = cap;
+        self.enforce_capacity();
+    }
     /// Reserves capacity for at least `additional` more entries.
     pub fn reserve(&mut self, additional: usize) {
         self.map.reserve(additional);
@@ -86,16 +135,90 @@ impl<K: Eq + Hash + Clone, V> OrdHash<K, V> {
             self.length += 1;
         }
         self.order.push_back(GenHolder{ value: cloned_key, generation: self.generation });
+        self.enforce_capacity();
+        self.maybe_compact();
+    }
+    /// Inserts a key-value pair like `push_back`, returning an evicted entry.
+    ///
+    /// When a capacity bound is set and the insert pushes the live length over
+    /// it, the oldest live entry is removed and returned as `Some((k, v))`.
+    /// Returns `None` when nothing was evicted.
+    pub fn push_back_evicting(&mut self, key: K, value: V) -> Option<(K, V)> {
+        let cloned_key = key.clone();
+        self.generation += 1;
+        if self.map.insert(key, GenHolder { value, generation: self.generation }).is_none() {
+            self.length += 1;
+        }
+        self.order.push_back(GenHolder{ value: cloned_key, generation: self.generation });
+        let evicted = if self.max_len.is_some_and(|max| self.length > max.get()) {
+            self.pop_front()
+        } else {
+            None
+        };
+        self.maybe_compact();
+        evicted
+    }
+    /// Rebuilds the order queue to hold exactly one entry per live key.
+    ///
+    /// Repeated overwrites and `refresh()` leave superseded order entries behind,
+    /// so `used_entries()` can grow without bound even while `len()` stays small.
+    /// This walks the order queue front-to-back, keeps only the entries whose
+    /// generation still matches their live map holder, and reassigns sequential
+    /// generations to the survivors (resetting the generation counter so it
+    /// cannot overflow). Relative order is preserved.
+    pub fn compact(&mut self) {
+        let mut compacted = VecDeque::with_capacity(self.length);
+        let mut next_gen = 0;
+        let old = std::mem::take(&mut self.order);
+        for mut holder in old {
+            if let Some(vh) = self.map.get_mut(&holder.value)
+                && vh.generation == holder.generation
+                && vh.generation != 0
+            {
+                next_gen += 1;
+                vh.generation = next_gen;
+                holder.generation = next_gen;
+                compacted.push_back(holder);
+            }
+        }
+        self.order = compacted;
+        self.generation = next_gen;
+    }
+    /// Compacts automatically once stale order entries dominate the queue.
+    fn maybe_compact(&mut self) {
+        if self.order.len() > 2 * self.length.max(8) {
+            self.compact();
+        }
+    }
+    /// Evicts the oldest live entries until the live length is within bound.
+    fn enforce_capacity(&mut self) {
+        if let Some(max) = self.max_len {
+            while self.length > max.get() {
+                self.pop_front();
+            }
+        }
     }
     /// Returns a reference to the value for `key`, if it is live.
     ///
     /// Keys marked unused via `mark_unused()` are treated as missing.
-    pub fn get(&self, key: &K) -> Option<&V> {
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
         if let Some(v) = self.map.get(key) && 0 != v.generation {
             return Some(&v.value)
         }
         None
     }
+    /// Returns `true` if `key` maps to a live entry.
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.get(key).is_some()
+    }
     /// Removes and returns the oldest live entry, if any.
     ///
     /// Stale or unused entries are skipped automatically.
@@ -138,7 +261,11 @@ impl<K: Eq + Hash + Clone, V> OrdHash<K, V> {
     ///
     /// After this call, `get()` returns `None` for `key`, and the entry is
     /// ignored by `peek_front()` and dropped by `pop_front()`.
-    pub fn mark_unused(&mut self, key: &K) -> Option<&V> {
+    pub fn mark_unused<Q>(&mut self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
         if let Some(vh) = self.map.get_mut(key) && vh.generation != 0 {
             vh.generation = 0;
             self.length -= 1;
@@ -150,23 +277,290 @@ impl<K: Eq + Hash + Clone, V> OrdHash<K, V> {
     ///
     /// If the entry was previously marked unused, it becomes live again and
     /// contributes to `len()`. Returns a reference to the value, if it exists.
-    pub fn refresh(&mut self, key: &K) -> Option<&V> {
-        if let Some(gh) = self.map.get_mut(key) {
-            if 0 == gh.generation {
-                self.length += 1;
+    pub fn refresh<Q>(&mut self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        // The order queue stores owned keys, so clone the one held by the map
+        // for the new order entry rather than demanding an owned argument.
+        let owned = self.map.get_key_value(key).map(|(k, _)| k.clone())?;
+        let gh = self.map.get_mut(key).unwrap();
+        if 0 == gh.generation {
+            self.length += 1;
+        }
+        self.generation += 1;
+        gh.generation = self.generation;
+        self.order.push_back(GenHolder{ value: owned, generation: self.generation });
+        self.enforce_capacity();
+        self.maybe_compact();
+        self.map.get(key).map(|gh| &gh.value)
+    }
+    /// Returns a reference to the value for `key`, moving it to the back.
+    ///
+    /// Behaves like `get`, but refreshes the key so that frequently accessed
+    /// entries survive eviction in a bounded (LRU) map. Unused keys stay unused
+    /// and yield `None`.
+    pub fn get_refresh<Q>(&mut self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        // Clone the owned key held by the map for the order queue, exactly as
+        // `refresh` does, so callers can refresh by a borrowed form of the key.
+        let owned = match self.map.get_key_value(key) {
+            Some((k, vh)) if vh.generation != 0 => k.clone(),
+            _ => return None,
+        };
+        self.generation += 1;
+        self.map.get_mut(key).unwrap().generation = self.generation;
+        self.order.push_back(GenHolder{ value: owned, generation: self.generation });
+        self.maybe_compact();
+        self.map.get(key).map(|gh| &gh.value)
+    }
+    /// Returns an iterator over the live entries in front-to-back order.
+    ///
+    /// Stale and unused order entries are skipped, so each live key is
+    /// yielded exactly once.
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        self.order.iter().filter_map(move |gh| {
+            self.map
+                .get(&gh.value)
+                .filter(|vh| vh.generation == gh.generation && vh.generation != 0)
+                .map(|vh| (&gh.value, &vh.value))
+        })
+    }
+    /// Returns an iterator over the live keys in front-to-back order.
+    pub fn keys(&self) -> impl Iterator<Item = &K> {
+        self.iter().map(|(k, _)| k)
+    }
+    /// Returns an iterator over the live values in front-to-back order.
+    pub fn values(&self) -> impl Iterator<Item = &V> {
+        self.iter().map(|(_, v)| v)
+    }
+    /// Returns an iterator yielding mutable references to the live values in
+    /// front-to-back order.
+    ///
+    /// Live entries appear in ascending generation order, which matches the
+    /// order in which they would be returned by `pop_front()`.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (&K, &mut V)> {
+        let mut live: Vec<_> = self
+            .map
+            .iter_mut()
+            .filter(|(_, vh)| vh.generation != 0)
+            .map(|(k, vh)| (vh.generation, k, &mut vh.value))
+            .collect();
+        live.sort_by_key(|(g, _, _)| *g);
+        live.into_iter().map(|(_, k, v)| (k, v))
+    }
+    /// Returns an iterator yielding mutable references to the live values in
+    /// front-to-back order.
+    pub fn values_mut(&mut self) -> impl Iterator<Item = &mut V> {
+        self.iter_mut().map(|(_, v)| v)
+    }
+    /// Gets the entry for `key` for in-place insertion or update.
+    ///
+    /// A present, live key yields `Entry::Occupied`; a missing or unused
+    /// (generation-0) key yields `Entry::Vacant`, so inserting through the
+    /// vacant entry re-enables an unused key exactly as `refresh()` would.
+    pub fn entry(&mut self, key: K) -> Entry<'_, K, V, S> {
+        if self.map.get(&key).is_some_and(|h| h.generation != 0) {
+            Entry::Occupied(OccupiedEntry { oh: self, key })
+        } else {
+            Entry::Vacant(VacantEntry { oh: self, key })
+        }
+    }
+}
+
+/// Owning iterator over an `OrdHash`, yielding live entries in order.
+///
+/// Created by the `IntoIterator` implementation for `OrdHash`.
+pub struct IntoIter<K, V, S = RandomState> {
+    inner: OrdHash<K, V, S>,
+}
+
+impl<K: Eq + Hash + Clone, V, S: BuildHasher> Iterator for IntoIter<K, V, S> {
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.pop_front()
+    }
+}
+
+impl<K: Eq + Hash + Clone, V, S: BuildHasher> IntoIterator for OrdHash<K, V, S> {
+    type Item = (K, V);
+    type IntoIter = IntoIter<K, V, S>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter { inner: self }
+    }
+}
+
+/// A view into a single entry in an `OrdHash`, which may be live or absent.
+///
+/// Constructed by `OrdHash::entry`.
+pub enum Entry<'a, K, V, S = RandomState> {
+    /// An entry for a present, live key.
+    Occupied(OccupiedEntry<'a, K, V, S>),
+    /// An entry for a missing or unused key.
+    Vacant(VacantEntry<'a, K, V, S>),
+}
+
+/// A view into a live entry in an `OrdHash`.
+pub struct OccupiedEntry<'a, K, V, S = RandomState> {
+    oh: &'a mut OrdHash<K, V, S>,
+    key: K,
+}
+
+/// A view into a vacant entry in an `OrdHash`.
+pub struct VacantEntry<'a, K, V, S = RandomState> {
+    oh: &'a mut OrdHash<K, V, S>,
+    key: K,
+}
+
+impl<'a, K: Eq + Hash + Clone, V, S: BuildHasher> Entry<'a, K, V, S> {
+    /// Ensures a value is present, inserting `default` if the entry is vacant.
+    pub fn or_insert(self, default: V) -> &'a mut V {
+        match self {
+            Entry::Occupied(e) => e.into_mut(),
+            Entry::Vacant(e) => e.insert(default),
+        }
+    }
+    /// Ensures a value is present, inserting the result of `default` otherwise.
+    pub fn or_insert_with<F: FnOnce() -> V>(self, default: F) -> &'a mut V {
+        match self {
+            Entry::Occupied(e) => e.into_mut(),
+            Entry::Vacant(e) => e.insert(default()),
+        }
+    }
+    /// Provides in-place mutable access to an occupied entry before any
+    /// potential insert.
+    pub fn and_modify<F: FnOnce(&mut V)>(mut self, f: F) -> Self {
+        if let Entry::Occupied(ref mut e) = self {
+            f(e.get_mut());
+        }
+        self
+    }
+}
+
+impl<'a, K: Eq + Hash + Clone, V: Default, S: BuildHasher> Entry<'a, K, V, S> {
+    /// Ensures a value is present, inserting `V::default()` if vacant.
+    pub fn or_default(self) -> &'a mut V {
+        self.or_insert_with(Default::default)
+    }
+}
+
+impl<'a, K: Eq + Hash + Clone, V, S: BuildHasher> OccupiedEntry<'a, K, V, S> {
+    /// Returns a reference to the value.
+    pub fn get(&self) -> &V {
+        &self.oh.map.get(&self.key).unwrap().value
+    }
+    /// Returns a mutable reference to the value.
+    pub fn get_mut(&mut self) -> &mut V {
+        &mut self.oh.map.get_mut(&self.key).unwrap().value
+    }
+    /// Converts the entry into a mutable reference bound to the map's lifetime.
+    pub fn into_mut(self) -> &'a mut V {
+        &mut self.oh.map.get_mut(&self.key).unwrap().value
+    }
+    /// Removes the entry and returns its value.
+    ///
+    /// The superseded order entry is left in place and skipped as stale.
+    pub fn remove(self) -> V {
+        let holder = self.oh.map.remove(&self.key).unwrap();
+        self.oh.length -= 1;
+        holder.value
+    }
+}
+
+impl<'a, K: Eq + Hash + Clone, V, S: BuildHasher> VacantEntry<'a, K, V, S> {
+    /// Inserts `value`, re-enabling the key and moving it to the back.
+    ///
+    /// This bumps the generation and appends to the order queue like
+    /// `push_back`, always increments `len()` since a vacant entry is never
+    /// counted as live, and enforces any capacity bound afterwards.
+    pub fn insert(self, value: V) -> &'a mut V {
+        let VacantEntry { oh, key } = self;
+        oh.generation += 1;
+        let generation = oh.generation;
+        oh.order.push_back(GenHolder { value: key.clone(), generation });
+        oh.length += 1;
+        let lookup_key = key.clone();
+        match oh.map.entry(key) {
+            std::collections::hash_map::Entry::Occupied(mut o) => {
+                o.insert(GenHolder { value, generation });
+            }
+            std::collections::hash_map::Entry::Vacant(v) => {
+                v.insert(GenHolder { value, generation });
             }
-            self.generation += 1;
-            gh.generation = self.generation;
-            self.order.push_back(GenHolder{ value: key.clone(), generation: self.generation });
-            return Some(&gh.value);
         }
-        None
+        oh.enforce_capacity();
+        oh.maybe_compact();
+        // The just-inserted key is the newest (back of the order), and the bound
+        // is always nonzero, so `enforce_capacity` only evicts older entries and
+        // never the one we return here.
+        &mut oh.map.get_mut(&lookup_key).unwrap().value
     }
-    
 }
 
-impl<K: Eq + Hash + Clone, V> Default for OrdHash<K, V> {
+impl<K: Eq + Hash + Clone, V, S: BuildHasher + Default> Default for OrdHash<K, V, S> {
     fn default() -> Self {
-        Self::new()
+        Self::with_hasher(S::default())
+    }
+}
+
+/// Serializes only the live entries, in front-to-back order, as a sequence of
+/// `(K, V)` pairs so that insertion order round-trips exactly.
+#[cfg(feature = "serde")]
+impl<K, V, S> serde::Serialize for OrdHash<K, V, S>
+where
+    K: serde::Serialize + Eq + Hash + Clone,
+    V: serde::Serialize,
+    S: BuildHasher,
+{
+    fn serialize<Ser: serde::Serializer>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error> {
+        serializer.collect_seq(self.iter())
+    }
+}
+
+/// Reconstructs the map by `push_back`-ing the deserialized pairs in order.
+#[cfg(feature = "serde")]
+impl<'de, K, V, S> serde::Deserialize<'de> for OrdHash<K, V, S>
+where
+    K: serde::Deserialize<'de> + Eq + Hash + Clone,
+    V: serde::Deserialize<'de>,
+    S: BuildHasher + Default,
+{
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct OrdHashVisitor<K, V, S> {
+            marker: std::marker::PhantomData<(K, V, S)>,
+        }
+        impl<'de, K, V, S> serde::de::Visitor<'de> for OrdHashVisitor<K, V, S>
+        where
+            K: serde::Deserialize<'de> + Eq + Hash + Clone,
+            V: serde::Deserialize<'de>,
+            S: BuildHasher + Default,
+        {
+            type Value = OrdHash<K, V, S>;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                f.write_str("a sequence of key-value pairs")
+            }
+
+            fn visit_seq<A: serde::de::SeqAccess<'de>>(
+                self,
+                mut seq: A,
+            ) -> Result<Self::Value, A::Error> {
+                let cap = seq.size_hint().unwrap_or(0);
+                let mut map = OrdHash::with_capacity_and_hasher(cap, S::default());
+                while let Some((k, v)) = seq.next_element()? {
+                    map.push_back(k, v);
+                }
+                Ok(map)
+            }
+        }
+        deserializer.deserialize_seq(OrdHashVisitor {
+            marker: std::marker::PhantomData,
+        })
     }
 }
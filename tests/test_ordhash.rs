@@ -1,4 +1,9 @@
 use ordhash::OrdHash;
+use std::num::NonZeroUsize;
+
+fn nz(n: usize) -> NonZeroUsize {
+    NonZeroUsize::new(n).unwrap()
+}
 
 #[test]
 fn new_is_empty() {
@@ -158,3 +163,222 @@ fn value_overwrite_and_peek_behaviour() {
         index += 1;
     }
 }
+
+#[test]
+fn iter_yields_live_entries_in_order() {
+    let mut m = OrdHash::new();
+    m.push_back(1, "one");
+    m.push_back(2, "two");
+    m.push_back(3, "three");
+    m.push_back(1, "one_updated");
+
+    let collected: Vec<_> = m.iter().map(|(k, v)| (*k, *v)).collect();
+    assert_eq!(collected, [(2, "two"), (3, "three"), (1, "one_updated")]);
+
+    let keys: Vec<_> = m.keys().copied().collect();
+    assert_eq!(keys, [2, 3, 1]);
+    let values: Vec<_> = m.values().copied().collect();
+    assert_eq!(values, ["two", "three", "one_updated"]);
+}
+
+#[test]
+fn values_mut_updates_in_order() {
+    let mut m = OrdHash::new();
+    m.push_back(1, 10);
+    m.push_back(2, 20);
+    m.push_back(1, 11);
+
+    let seen: Vec<_> = m.values_mut().map(|v| { *v += 1; *v }).collect();
+    assert_eq!(seen, [21, 12]);
+    assert_eq!(m.get(&1), Some(&12));
+    assert_eq!(m.get(&2), Some(&21));
+}
+
+#[test]
+fn into_iter_consumes_in_order() {
+    let mut m = OrdHash::new();
+    m.push_back(1, "a");
+    m.push_back(2, "b");
+    let collected: Vec<_> = m.into_iter().collect();
+    assert_eq!(collected, [(1, "a"), (2, "b")]);
+}
+
+#[test]
+fn with_max_len_evicts_oldest_live() {
+    let mut m = OrdHash::with_max_len(nz(2));
+    m.push_back(1, "a");
+    m.push_back(2, "b");
+    m.push_back(3, "c");
+
+    assert_eq!(m.len(), 2);
+    assert_eq!(m.get(&1), None);
+    assert_eq!(m.get(&2), Some(&"b"));
+    assert_eq!(m.get(&3), Some(&"c"));
+    assert_eq!(m.peek_front(), Some((&2, &"b")));
+}
+
+#[test]
+fn push_back_evicting_returns_evicted_and_reclaims_stale() {
+    let mut m = OrdHash::with_max_len(nz(2));
+    assert_eq!(m.push_back_evicting(1, "a"), None);
+    assert_eq!(m.push_back_evicting(2, "b"), None);
+    assert_eq!(m.push_back_evicting(3, "c"), Some((1, "a")));
+    assert_eq!(m.len(), 2);
+
+    let mut m = OrdHash::with_max_len(nz(1));
+    m.push_back_evicting(1, "a");
+    for _ in 0..100 {
+        m.push_back_evicting(1, "a");
+    }
+    assert_eq!(m.len(), 1);
+    assert!(m.used_entries() <= 16);
+}
+
+#[test]
+fn compact_preserves_order_and_shrinks() {
+    let mut m = OrdHash::new();
+    m.push_back(1, "one");
+    m.push_back(2, "two");
+    m.push_back(3, "three");
+    m.push_back(1, "one_v2");
+    m.refresh(&2);
+    assert_eq!(m.used_entries(), 5);
+
+    m.compact();
+    assert_eq!(m.used_entries(), 3);
+    assert_eq!(m.len(), 3);
+
+    let order: Vec<_> = m.iter().map(|(k, _)| *k).collect();
+    assert_eq!(order, [3, 1, 2]);
+    assert_eq!(m.get(&1), Some(&"one_v2"));
+}
+
+#[test]
+fn entry_or_insert_with_inserts_once() {
+    let mut m = OrdHash::new();
+    assert_eq!(m.entry(1).or_insert_with(|| "a"), &"a");
+    assert_eq!(m.entry(1).or_insert_with(|| "b"), &"a");
+    assert_eq!(m.len(), 1);
+}
+
+#[test]
+fn entry_reenables_unused_without_double_counting() {
+    let mut m = OrdHash::new();
+    m.push_back(1, "one");
+    m.push_back(2, "two");
+    assert_eq!(m.mark_unused(&1), Some(&"one"));
+    assert_eq!(m.len(), 1);
+
+    m.entry(1).or_insert("one_again");
+    assert_eq!(m.len(), 2);
+    assert_eq!(m.get(&1), Some(&"one_again"));
+
+    *m.entry(1).or_insert("unused") = "one_final";
+    assert_eq!(m.len(), 2);
+    assert_eq!(m.get(&1), Some(&"one_final"));
+}
+
+#[test]
+fn entry_insert_respects_capacity_bound() {
+    let mut m = OrdHash::with_max_len(nz(2));
+    m.entry(1).or_insert("a");
+    m.entry(2).or_insert("b");
+    m.entry(3).or_insert("c");
+    assert_eq!(m.len(), 2);
+    assert_eq!(m.get(&1), None);
+    assert_eq!(m.get(&3), Some(&"c"));
+}
+
+#[test]
+fn entry_insert_into_minimal_bound_returns_valid_ref() {
+    let mut m: OrdHash<i32, i32> = OrdHash::with_max_len(nz(1));
+    *m.entry(1).or_insert(10) += 5;
+    assert_eq!(m.len(), 1);
+    assert_eq!(m.get(&1), Some(&15));
+
+    // Inserting a second key evicts the first, yet still returns a live ref.
+    *m.entry(2).or_insert(20) += 1;
+    assert_eq!(m.len(), 1);
+    assert_eq!(m.get(&1), None);
+    assert_eq!(m.get(&2), Some(&21));
+
+    // Re-applying the same bound via `set_capacity` evicts nothing.
+    m.set_capacity(Some(nz(1)));
+    assert_eq!(m.len(), 1);
+}
+
+#[test]
+fn occupied_entry_remove_decrements_length() {
+    let mut m = OrdHash::new();
+    m.push_back(1, "one");
+    m.push_back(2, "two");
+    if let ordhash::Entry::Occupied(e) = m.entry(1) {
+        assert_eq!(e.remove(), "one");
+    } else {
+        panic!("expected occupied entry");
+    }
+    assert_eq!(m.len(), 1);
+    assert_eq!(m.get(&1), None);
+}
+
+#[test]
+fn with_hasher_preserves_behavior() {
+    use std::collections::hash_map::RandomState;
+    let mut m: OrdHash<i32, &str, RandomState> = OrdHash::with_hasher(RandomState::new());
+    m.push_back(1, "a");
+    m.push_back(2, "b");
+    m.push_back(1, "a2");
+
+    assert_eq!(m.get(&1), Some(&"a2"));
+    let order: Vec<_> = m.keys().copied().collect();
+    assert_eq!(order, [2, 1]);
+}
+
+#[test]
+fn borrow_lookups_on_string_keys() {
+    let mut m: OrdHash<String, i32> = OrdHash::new();
+    m.push_back("one".to_string(), 1);
+    m.push_back("two".to_string(), 2);
+
+    assert_eq!(m.get("one"), Some(&1));
+    assert!(m.contains_key("two"));
+    assert!(!m.contains_key("three"));
+
+    assert_eq!(m.mark_unused("one"), Some(&1));
+    assert_eq!(m.get("one"), None);
+    assert_eq!(m.refresh("one"), Some(&1));
+    assert_eq!(m.len(), 2);
+    assert_eq!(m.get_refresh("two"), Some(&2));
+}
+
+#[test]
+fn get_refresh_keeps_key_alive_under_eviction() {
+    let mut m = OrdHash::with_max_len(nz(2));
+    m.push_back(1, "a");
+    m.push_back(2, "b");
+    assert_eq!(m.get_refresh(&1), Some(&"a"));
+    m.push_back(3, "c");
+
+    assert_eq!(m.get(&2), None);
+    assert_eq!(m.get(&1), Some(&"a"));
+    assert_eq!(m.get(&3), Some(&"c"));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_roundtrip_preserves_order() {
+    let mut m = OrdHash::new();
+    m.push_back(1, "one".to_string());
+    m.push_back(2, "two".to_string());
+    m.push_back(1, "one_updated".to_string());
+
+    let json = serde_json::to_string(&m).unwrap();
+    let back: OrdHash<i32, String> = serde_json::from_str(&json).unwrap();
+
+    let order: Vec<_> = back.iter().map(|(k, v)| (*k, v.clone())).collect();
+    assert_eq!(
+        order,
+        [(2, "two".to_string()), (1, "one_updated".to_string())]
+    );
+    assert_eq!(back.len(), 2);
+}